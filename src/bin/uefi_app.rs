@@ -0,0 +1,134 @@
+// Copyright 2025 Johanna Sörngård
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Pre-boot firmware build of the detector.
+//!
+//! Build with `--no-default-features --features uefi --target x86_64-unknown-uefi`.
+//! Running before the OS is up removes confounders the hosted build suffers
+//! from: the OS relocating or scrubbing pages, swapping the detector mass
+//! out to disk, and available-memory reporting that fights with the page
+//! cache. There's no `sysinfo` here, so the detector size is fixed rather
+//! than queried.
+//!
+//! A `#![cfg(feature = "uefi")]` at the crate root would only cfg away the items in this
+//! file; it wouldn't stop Cargo from compiling this `[[bin]]` target at all, so a plain
+//! `cargo build` would still demand a `main` function here. Once this tree has a `Cargo.toml`, this target
+//! should also be marked `required-features = ["uefi"]` so hosted builds skip it entirely;
+//! until then, the `#[cfg(not(feature = "uefi"))]` stub below keeps default-feature builds
+//! compiling.
+
+#![cfg_attr(feature = "uefi", no_std)]
+#![cfg_attr(feature = "uefi", no_main)]
+
+#[cfg(feature = "uefi")]
+extern crate alloc;
+
+#[cfg(feature = "uefi")]
+use core::fmt::Write;
+#[cfg(feature = "uefi")]
+use core::time::Duration;
+
+#[cfg(feature = "uefi")]
+use cosmic_ray_detection::detector::{Detector, UpsetKind};
+#[cfg(feature = "uefi")]
+use uefi::prelude::*;
+#[cfg(feature = "uefi")]
+use uefi::{boot, system};
+
+/// How long to wait between integrity checks.
+#[cfg(feature = "uefi")]
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Size of the detector memory, in bytes.
+///
+/// Firmware can't tell us "available" memory the way a running OS can, so
+/// rather than guess we ask for a fixed, conservative amount up front.
+#[cfg(feature = "uefi")]
+const DETECTOR_SIZE: usize = 64 * 1024 * 1024;
+
+#[cfg(feature = "uefi")]
+#[entry]
+fn efi_main() -> Status {
+    uefi::helpers::init().expect("failed to initialize UEFI boot services");
+
+    let mut detector = Detector::new(0, DETECTOR_SIZE);
+    let mut checks: u64 = 1;
+
+    let _ = system::with_stdout(|stdout| {
+        writeln!(
+            stdout,
+            "cosmic_ray_detection (UEFI) monitoring {DETECTOR_SIZE} bytes"
+        )
+    });
+
+    loop {
+        detector.reset();
+
+        let mut memory_is_intact = true;
+        while memory_is_intact {
+            boot::stall(CHECK_INTERVAL.as_micros() as usize);
+            memory_is_intact = detector.is_intact();
+            if memory_is_intact {
+                let _ = system::with_stdout(|stdout| {
+                    writeln!(stdout, "Passed integrity check number {checks}")
+                });
+            }
+            checks += 1;
+        }
+
+        let _ = system::with_stdout(|stdout| {
+            writeln!(
+                stdout,
+                "Detected a bitflip on integrity check number {}",
+                // `checks` has already been advanced past the check that failed.
+                checks - 1
+            )
+        });
+
+        let upsets = detector.find_upsets();
+
+        if upsets.is_empty() {
+            let _ = system::with_stdout(|stdout| {
+                writeln!(
+                    stdout,
+                    "The same bit flipped back before we could find which one it was! Incredible!"
+                )
+            });
+        } else {
+            let mut single_bit: u64 = 0;
+            let mut multi_bit: u64 = 0;
+            let mut zero_to_one: u64 = 0;
+            let mut one_to_zero: u64 = 0;
+
+            for upset in &upsets {
+                let _ = system::with_stdout(|stdout| {
+                    writeln!(
+                        stdout,
+                        "The byte at index {} flipped from {} to {}",
+                        upset.index, upset.old_value, upset.new_value,
+                    )
+                });
+                match upset.kind {
+                    UpsetKind::SingleBit => single_bit += 1,
+                    UpsetKind::MultiBit => multi_bit += 1,
+                }
+                zero_to_one += u64::from(upset.zero_to_one_count());
+                one_to_zero += u64::from(upset.one_to_zero_count());
+            }
+
+            let _ = system::with_stdout(|stdout| {
+                writeln!(
+                    stdout,
+                    "{single_bit} single-bit upset(s), {multi_bit} multi-bit upset(s) ({zero_to_one} bit(s) flipped 0→1, {one_to_zero} bit(s) flipped 1→0)",
+                )
+            });
+        }
+    }
+}
+
+/// Without the `uefi` feature this binary has nothing to do: the real entry point,
+/// `efi_main`, only exists when that feature is on. This stub exists so that
+/// `cargo build`/`check`/`test` with the default feature set still succeeds instead of
+/// failing with "`main` function not found".
+#[cfg(not(feature = "uefi"))]
+fn main() {}
@@ -77,6 +77,13 @@ pub struct Cli {
     /// The number of parallel jobs to run when writing and reading the detector memory.
     /// If this is not set the number of jobs will be set to the number of logical cores.
     pub jobs: Option<NonZeroUsize>,
+
+    #[cfg(feature = "net")]
+    #[arg(long, value_name = "ADDR")]
+    /// Report every passed integrity check and every detected upset to a collector at this
+    /// address over UDP, so a fleet of detectors can be aggregated into one dashboard.
+    /// Send failures are logged and otherwise ignored, so a flaky network never stops detection.
+    pub report: Option<std::net::SocketAddr>,
 }
 
 /// Parses a string describing a number of bytes into an integer.
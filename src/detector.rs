@@ -1,15 +1,104 @@
-use std::ptr::{read_volatile, write_volatile};
+use core::ptr::{read_volatile, write_volatile};
 
-#[cfg(all(not(target_os = "windows"), not(target_os = "freebsd")))]
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+
+#[cfg(all(feature = "std", not(target_os = "windows"), not(target_os = "freebsd")))]
 use crate::config::AllocationMode;
 
 #[cfg(feature = "rayon")]
-use rayon::prelude::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
+use rayon::prelude::{IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 
+#[cfg(feature = "std")]
 use sysinfo::{MemoryRefreshKind, RefreshKind, System};
 
+/// A `*const usize` that's `Sync`, so it can be captured by a `rayon` parallel closure.
+///
+/// Sound here because every index the closure is given indexes a distinct, in-bounds word,
+/// so no two threads ever read through overlapping memory, and all reads go through
+/// [`read_volatile`], never a plain load or a write.
+///
+/// The pointer is only reachable through [`SyncPtr::add`], an inherent method rather than a
+/// public field: Rust 2021's disjoint closure captures would otherwise let a closure that
+/// only touches `.0` capture the bare `*const usize` instead of the `Sync` wrapper around it.
+#[cfg(feature = "rayon")]
+struct SyncPtr(*const usize);
+
+#[cfg(feature = "rayon")]
+unsafe impl Sync for SyncPtr {}
+
+#[cfg(feature = "rayon")]
+impl SyncPtr {
+    /// # Safety
+    /// Same contract as [`<*const usize>::add`](pointer::add): `count` must keep the
+    /// resulting pointer in bounds of the same allocated object.
+    unsafe fn add(&self, count: usize) -> *const usize {
+        unsafe { self.0.add(count) }
+    }
+}
+
+/// Whether an [`Upset`] looks like it came from a single bit flipping, or from several bits
+/// flipping at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsetKind {
+    /// Exactly one bit changed in this byte, and no neighboring byte in the same machine
+    /// word changed alongside it.
+    SingleBit,
+    /// Either more than one bit changed in this byte, or a neighboring byte in the same
+    /// machine word changed alongside it. Either is a signature of a single particle
+    /// depositing charge across more than one memory cell.
+    MultiBit,
+}
+
+/// A single changed byte found while scanning the detector mass, decoded into which bits
+/// flipped and whether the event looks like a single- or multi-bit upset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Upset {
+    /// Index of the changed byte within the detector mass.
+    pub index: usize,
+    /// The byte's value before the flip.
+    pub old_value: u8,
+    /// The byte's value after the flip.
+    pub new_value: u8,
+    /// Whether this looks like a single- or multi-bit upset.
+    pub kind: UpsetKind,
+}
+
+impl Upset {
+    /// The bits that changed, as a mask (`old_value ^ new_value`).
+    pub const fn flipped_bits(&self) -> u8 {
+        self.old_value ^ self.new_value
+    }
+
+    /// The number of bits that flipped from 0 to 1.
+    pub const fn zero_to_one_count(&self) -> u32 {
+        (self.flipped_bits() & self.new_value).count_ones()
+    }
+
+    /// The number of bits that flipped from 1 to 0.
+    pub const fn one_to_zero_count(&self) -> u32 {
+        (self.flipped_bits() & self.old_value).count_ones()
+    }
+}
+
+/// Number of bytes in a `usize`, i.e. the width of a word-at-a-time scan.
+const WORD: usize = core::mem::size_of::<usize>();
+
+/// The geometry of a `usize`-at-a-time scan over the detector mass: the `head` bytes at the
+/// start and `tail` bytes at the end that don't form a full aligned word and so have to be
+/// checked one at a time, and the `word_count` aligned words in between, reachable through
+/// `words_ptr` (itself `WORD`-aligned) and compared against `word_pattern` (`default`
+/// repeated in every byte of a word). [`Detector::position_of_changed_element`] and
+/// [`Detector::find_upsets`] both walk this same shape; only what they do with a changed
+/// word differs (stop at the first one vs. decode every changed byte in it).
+struct WordScanLayout {
+    head: usize,
+    tail: usize,
+    word_count: usize,
+    words_ptr: *const usize,
+    word_pattern: usize,
+}
+
 /// In order to prevent the optimizer from removing the reads of the memory that make up the detector
 /// this struct will only use volatile reads and writes to its memory.
 pub struct Detector {
@@ -25,7 +114,7 @@ impl Detector {
         }
     }
 
-    #[cfg(any(target_os = "windows", target_os = "freebsd"))]
+    #[cfg(all(feature = "std", any(target_os = "windows", target_os = "freebsd")))]
     /// Creates a new detector that fills up as much memory as possible.
     pub fn new_with_maximum_size(default: u8) -> Self {
         // Know this is supported on windows.
@@ -40,7 +129,7 @@ impl Detector {
         }
     }
 
-    #[cfg(all(not(target_os = "windows"), not(target_os = "freebsd")))]
+    #[cfg(all(feature = "std", not(target_os = "windows"), not(target_os = "freebsd")))]
     /// Creates a new detector that fills up as much memory as possible in the specified way.
     /// # Panic
     /// Panics if this function is called on an operating system that is not supported by [sysinfo](https://crates.io/crates/sysinfo).
@@ -80,6 +169,27 @@ impl Detector {
         self.default
     }
 
+    /// Computes the geometry of a `usize`-at-a-time scan over the detector mass. See
+    /// [`WordScanLayout`].
+    fn word_scan_layout(&self) -> WordScanLayout {
+        let len = self.detector_mass.len();
+        let head = self.detector_mass.as_ptr().align_offset(WORD).min(len);
+        let tail = (len - head) % WORD;
+        let word_count = (len - head - tail) / WORD;
+        // SAFETY: every index in `0..word_count` lands on a `WORD`-aligned, in-bounds word of
+        // `detector_mass`, since the pointer is advanced past the unaligned head first.
+        let words_ptr = unsafe { self.detector_mass.as_ptr().add(head) } as *const usize;
+        let word_pattern = usize::from_ne_bytes([self.default; WORD]);
+
+        WordScanLayout {
+            head,
+            tail,
+            word_count,
+            words_ptr,
+            word_pattern,
+        }
+    }
+
     /// Writes the given value to every element of the detector memory.
     pub fn write(&mut self, value: u8) {
         #[cfg(feature = "rayon")]
@@ -94,17 +204,60 @@ impl Detector {
     }
 
     /// If an element in the detector does not match its default value, return its index.
+    ///
+    /// Scans `usize`-at-a-time rather than one byte at a time: each aligned word of the
+    /// detector mass is read with a single volatile load and compared against a word made up
+    /// of [`default`](Detector::default) repeated in every byte, so a clean word costs one
+    /// load per [`size_of::<usize>()`](core::mem::size_of) bytes instead of one per byte.
+    /// A word that differs from that pattern means a flip is somewhere in that word; only then do we look at its
+    /// individual bytes to find which one changed. The handful of bytes at the start and
+    /// end of the buffer that can't form a full aligned word are still checked one at a time.
     pub fn position_of_changed_element(&self) -> Option<usize> {
-        #[cfg(feature = "rayon")]
-        return self
-            .detector_mass
-            .par_iter()
-            .position_any(|r| unsafe { read_volatile(r) != self.default });
+        let len = self.detector_mass.len();
+        let layout = self.word_scan_layout();
 
-        #[cfg(not(feature = "rayon"))]
-        self.detector_mass
+        if let Some(i) = self.detector_mass[..layout.head]
             .iter()
             .position(|r| unsafe { read_volatile(r) != self.default })
+        {
+            return Some(i);
+        }
+
+        let changed_word = {
+            #[cfg(feature = "rayon")]
+            {
+                let words_ptr = SyncPtr(layout.words_ptr);
+                (0..layout.word_count).into_par_iter().find_any(|&i| unsafe {
+                    read_volatile(words_ptr.add(i)) != layout.word_pattern
+                })
+            }
+
+            #[cfg(not(feature = "rayon"))]
+            {
+                (0..layout.word_count).find(|&i| unsafe {
+                    read_volatile(layout.words_ptr.add(i)) != layout.word_pattern
+                })
+            }
+        };
+
+        if let Some(i) = changed_word {
+            let word = unsafe { read_volatile(layout.words_ptr.add(i)) };
+            // Byte-wise rather than `trailing_zeros() / 8`: the latter counts from the
+            // integer's LSB, which only lines up with the lowest memory address on a
+            // little-endian target. `to_ne_bytes` gives the bytes in memory order on any target.
+            let changed_byte = word
+                .to_ne_bytes()
+                .iter()
+                .zip(layout.word_pattern.to_ne_bytes())
+                .position(|(new, old)| *new != old)
+                .expect("word != word_pattern, so some byte must differ");
+            return Some(layout.head + i * WORD + changed_byte);
+        }
+
+        self.detector_mass[len - layout.tail..]
+            .iter()
+            .position(|r| unsafe { read_volatile(r) != self.default })
+            .map(|i| len - layout.tail + i)
     }
 
     /// If an element in the detector does not match its default value, return its index and value.
@@ -115,6 +268,91 @@ impl Detector {
         }
     }
 
+    /// Scans the *entire* detector mass and returns every changed byte, decoded into which
+    /// bits flipped and classified as a single- or multi-bit upset.
+    ///
+    /// Unlike [`position_of_changed_element`](Detector::position_of_changed_element), which
+    /// returns as soon as it finds the first mismatch, this walks the whole buffer so that a
+    /// trip that flipped more than one byte is fully characterized instead of just reported
+    /// as "something changed". This is meant to be called once, after
+    /// [`is_intact`](Detector::is_intact) has already reported a flip, not from the hot
+    /// per-check loop.
+    pub fn find_upsets(&self) -> Vec<Upset> {
+        let len = self.detector_mass.len();
+        let layout = self.word_scan_layout();
+        let default_bytes = layout.word_pattern.to_ne_bytes();
+
+        let mut upsets = Vec::new();
+        self.push_byte_upsets(0, &self.detector_mass[..layout.head], &mut upsets);
+
+        for i in 0..layout.word_count {
+            let word = unsafe { read_volatile(layout.words_ptr.add(i)) };
+            if word == layout.word_pattern {
+                continue;
+            }
+
+            let new_bytes = word.to_ne_bytes();
+            let changed_in_word = new_bytes
+                .iter()
+                .zip(&default_bytes)
+                .filter(|(new, old)| new != old)
+                .count();
+
+            for (byte_offset, (&new, &old)) in new_bytes.iter().zip(&default_bytes).enumerate() {
+                if new != old {
+                    let kind = if changed_in_word > 1 || (old ^ new).count_ones() > 1 {
+                        UpsetKind::MultiBit
+                    } else {
+                        UpsetKind::SingleBit
+                    };
+                    upsets.push(Upset {
+                        index: layout.head + i * WORD + byte_offset,
+                        old_value: old,
+                        new_value: new,
+                        kind,
+                    });
+                }
+            }
+        }
+
+        self.push_byte_upsets(
+            len - layout.tail,
+            &self.detector_mass[len - layout.tail..],
+            &mut upsets,
+        );
+
+        upsets
+    }
+
+    /// Checks a (presumably unaligned head or tail) byte slice one element at a time and
+    /// pushes an [`Upset`] for every element that doesn't match `self.default`. A head or
+    /// tail lies within a single machine word, so if more than one of its bytes changed,
+    /// all of them are classified as [`UpsetKind::MultiBit`].
+    fn push_byte_upsets(&self, offset: usize, bytes: &[u8], upsets: &mut Vec<Upset>) {
+        let first = upsets.len();
+        for (i, cell) in bytes.iter().enumerate() {
+            let new = unsafe { read_volatile(cell) };
+            if new != self.default {
+                let kind = if (self.default ^ new).count_ones() > 1 {
+                    UpsetKind::MultiBit
+                } else {
+                    UpsetKind::SingleBit
+                };
+                upsets.push(Upset {
+                    index: offset + i,
+                    old_value: self.default,
+                    new_value: new,
+                    kind,
+                });
+            }
+        }
+        if upsets.len() - first > 1 {
+            for upset in &mut upsets[first..] {
+                upset.kind = UpsetKind::MultiBit;
+            }
+        }
+    }
+
     /// Resets the detector to its default value.
     pub fn reset(&mut self) {
         if self.default == 0 {
@@ -133,3 +371,91 @@ impl Detector {
             .map(|reference| unsafe { read_volatile(reference) })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn new_detector_is_intact() {
+        let d = Detector::new(7, 200);
+        assert!(d.is_intact());
+        assert_eq!(d.position_of_changed_element(), None);
+        assert!(d.find_upsets().is_empty());
+    }
+
+    #[test]
+    fn finds_flip_in_buffer_smaller_than_a_word() {
+        // Smaller than a word, so there is no full aligned word: every byte is checked one at
+        // a time as part of the unaligned head or tail, depending on where the allocation starts.
+        let mut d = Detector::new(0, WORD - 1);
+        d.detector_mass[1] = 1;
+        assert_eq!(d.position_of_changed_element(), Some(1));
+    }
+
+    #[test]
+    fn finds_flip_in_aligned_word() {
+        let mut d = Detector::new(0, 4 * WORD);
+        d.detector_mass[WORD + 2] = 5;
+        assert_eq!(d.position_of_changed_element(), Some(WORD + 2));
+    }
+
+    #[test]
+    fn finds_flip_in_unaligned_tail() {
+        let mut d = Detector::new(0, 3 * WORD + 1);
+        let last = d.len() - 1;
+        d.detector_mass[last] = 3;
+        assert_eq!(d.position_of_changed_element(), Some(last));
+    }
+
+    #[test]
+    fn classifies_single_bit_upset() {
+        let mut d = Detector::new(0, 4 * WORD);
+        d.detector_mass[5] = 0b0000_0001;
+
+        let upsets = d.find_upsets();
+
+        assert_eq!(upsets.len(), 1);
+        assert_eq!(upsets[0].index, 5);
+        assert_eq!(upsets[0].kind, UpsetKind::SingleBit);
+        assert_eq!(upsets[0].zero_to_one_count(), 1);
+        assert_eq!(upsets[0].one_to_zero_count(), 0);
+    }
+
+    #[test]
+    fn classifies_multiple_flipped_bits_in_one_byte_as_multi_bit() {
+        let mut d = Detector::new(0, 4 * WORD);
+        d.detector_mass[5] = 0b0000_0011;
+
+        let upsets = d.find_upsets();
+
+        assert_eq!(upsets.len(), 1);
+        assert_eq!(upsets[0].kind, UpsetKind::MultiBit);
+    }
+
+    #[test]
+    fn classifies_adjacent_bytes_in_the_same_word_as_multi_bit() {
+        let mut d = Detector::new(0, 4 * WORD);
+        d.detector_mass[WORD] = 1;
+        d.detector_mass[WORD + 1] = 1;
+
+        let upsets = d.find_upsets();
+
+        assert_eq!(upsets.len(), 2);
+        assert!(upsets.iter().all(|u| u.kind == UpsetKind::MultiBit));
+    }
+
+    #[test]
+    fn find_upsets_does_not_stop_at_the_first_change() {
+        let mut d = Detector::new(0, 4 * WORD);
+        d.detector_mass[0] = 1;
+        let last = d.len() - 1;
+        d.detector_mass[last] = 1;
+
+        let upsets = d.find_upsets();
+
+        assert_eq!(upsets.len(), 2);
+        assert_eq!(upsets[0].index, 0);
+        assert_eq!(upsets[1].index, last);
+    }
+}
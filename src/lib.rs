@@ -0,0 +1,22 @@
+// Copyright 2025 Johanna Sörngård
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Core detector logic, usable with or without the standard library.
+//!
+//! With the `std` feature (on by default) this also builds the pieces that
+//! need an operating system: the `sysinfo`-backed memory queries in
+//! [`detector`], and the `clap`-based CLI parser in [`config`]. Without it,
+//! only [`detector::Detector`] is built, backed by nothing but `alloc`, so
+//! it can run somewhere no conventional OS does, such as a `uefi` pre-boot
+//! application.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod config;
+pub mod detector;
+#[cfg(all(feature = "std", feature = "net"))]
+pub mod reporter;
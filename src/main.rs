@@ -1,3 +1,10 @@
+// The hosted CLI only makes sense with the standard library: it needs
+// `clap`, `chrono`, and (on most platforms) `sysinfo` to query available
+// memory. The `no_std` build of `Detector` lives in the library crate and
+// is driven instead by the `uefi` binary.
+#[cfg(not(feature = "std"))]
+compile_error!("the cosmic_ray_detection binary requires the `std` feature");
+
 use std::error::Error;
 use std::io::{stdout, Write};
 use std::thread::sleep;
@@ -7,13 +14,15 @@ use chrono::Local;
 use clap::Parser;
 use humantime::format_duration;
 
-mod config;
-mod detector;
-
 #[cfg(all(not(target_os = "windows"), not(target_os = "freebsd")))]
-use crate::config::AllocationMode;
+use cosmic_ray_detection::config::AllocationMode;
 
-use crate::{config::Cli, detector::Detector};
+#[cfg(feature = "net")]
+use cosmic_ray_detection::reporter::Reporter;
+use cosmic_ray_detection::{
+    config::Cli,
+    detector::{Detector, UpsetKind},
+};
 
 fn main() -> Result<(), Box<dyn Error>> {
     let conf = Cli::parse();
@@ -77,6 +86,14 @@ fn main() -> Result<(), Box<dyn Error>> {
         println!("\nBeginning detection loop");
     }
 
+    // A fleet of detectors can report heartbeats and upsets to a central collector.
+    // Nothing about the detection loop below depends on whether this is set.
+    #[cfg(feature = "net")]
+    let reporter = match conf.report {
+        Some(addr) => Some(Reporter::connect(addr)?),
+        None => None,
+    };
+
     let mut checks: u64 = 1;
     let mut memory_is_intact: bool;
     let start: Instant = Instant::now();
@@ -110,6 +127,11 @@ fn main() -> Result<(), Box<dyn Error>> {
                     println!();
                 }
                 stdout().flush()?;
+
+                #[cfg(feature = "net")]
+                if let Some(reporter) = &reporter {
+                    reporter.report_heartbeat(checks, detector.len());
+                }
             }
             checks += 1;
         }
@@ -120,14 +142,41 @@ fn main() -> Result<(), Box<dyn Error>> {
             Local::now(),
         );
 
-        match detector.position_and_value_of_changed_element() {
-            Some((index, value)) => println!(
-                "The byte at index {index} flipped from {} to {value}",
-                detector.default(),
-            ),
-            None => println!(
+        let upsets = detector.find_upsets();
+
+        if upsets.is_empty() {
+            println!(
                 "The same bit flipped back before we could find which one it was! Incredible!"
-            ),
+            );
+        } else {
+            let mut single_bit: u64 = 0;
+            let mut multi_bit: u64 = 0;
+            let mut zero_to_one: u64 = 0;
+            let mut one_to_zero: u64 = 0;
+
+            for upset in &upsets {
+                println!(
+                    "The byte at index {} flipped from {} to {}",
+                    upset.index, upset.old_value, upset.new_value,
+                );
+
+                #[cfg(feature = "net")]
+                if let Some(reporter) = &reporter {
+                    // `checks` has already been advanced past the check that failed.
+                    reporter.report_upset(checks - 1, detector.len(), upset);
+                }
+
+                match upset.kind {
+                    UpsetKind::SingleBit => single_bit += 1,
+                    UpsetKind::MultiBit => multi_bit += 1,
+                }
+                zero_to_one += u64::from(upset.zero_to_one_count());
+                one_to_zero += u64::from(upset.one_to_zero_count());
+            }
+
+            println!(
+                "{single_bit} single-bit upset(s), {multi_bit} multi-bit upset(s) ({zero_to_one} bit(s) flipped 0→1, {one_to_zero} bit(s) flipped 1→0)",
+            );
         }
     }
 }
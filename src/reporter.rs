@@ -0,0 +1,174 @@
+// Copyright 2025 Johanna Sörngård
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! UDP telemetry for aggregating a fleet of detectors into one monitoring dashboard.
+//!
+//! Every passed integrity check (a heartbeat) and every detected [`Upset`] is encoded as a
+//! small datagram and sent to a single remote collector. A [`Reporter`] never treats a send
+//! failure as fatal: it logs to stderr and carries on, so a flaky network never stops
+//! detection.
+
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::detector::Upset;
+
+/// Wire format version. Bump this if the datagram layout documented on [`Reporter`] changes.
+const PROTOCOL_VERSION: u8 = 1;
+
+const KIND_HEARTBEAT: u8 = 0;
+const KIND_UPSET: u8 = 1;
+
+/// Reports heartbeats and upsets to a remote collector over UDP.
+///
+/// Datagrams share a header of:
+/// - 1 byte: protocol version ([`PROTOCOL_VERSION`])
+/// - 1 byte: message kind, 0 for a heartbeat or 1 for an upset
+/// - 1 byte: length of the hostname that follows, in bytes (truncated to 255)
+/// - that many bytes: the hostname, UTF-8, not nul-terminated
+/// - 8 bytes: monotonic integrity check counter, big-endian
+/// - 8 bytes: detector allocation size in bytes, big-endian
+///
+/// An upset datagram has four more fields appended: the byte index of the changed element
+/// (8 bytes, big-endian), its value before the flip, its value after the flip, and a mask of
+/// which bits flipped (one byte each).
+pub struct Reporter {
+    socket: UdpSocket,
+    hostname: String,
+}
+
+impl Reporter {
+    /// Binds an ephemeral local UDP socket and connects it to `collector`, so that later
+    /// sends only need to hand over a payload.
+    pub fn connect(collector: SocketAddr) -> std::io::Result<Self> {
+        let bind_addr: SocketAddr = if collector.is_ipv6() {
+            "[::]:0".parse().unwrap()
+        } else {
+            "0.0.0.0:0".parse().unwrap()
+        };
+        let socket = UdpSocket::bind(bind_addr)?;
+        socket.connect(collector)?;
+
+        Ok(Reporter {
+            socket,
+            hostname: sysinfo::System::host_name().unwrap_or_default(),
+        })
+    }
+
+    /// Reports that integrity check number `check` passed on a detector holding
+    /// `allocation_bytes` bytes.
+    pub fn report_heartbeat(&self, check: u64, allocation_bytes: usize) {
+        let mut datagram = Vec::with_capacity(19 + self.hostname.len());
+        self.push_header(&mut datagram, KIND_HEARTBEAT, check, allocation_bytes);
+        self.send(&datagram, "heartbeat");
+    }
+
+    /// Reports an [`Upset`] found on integrity check number `check`, on a detector holding
+    /// `allocation_bytes` bytes.
+    pub fn report_upset(&self, check: u64, allocation_bytes: usize, upset: &Upset) {
+        let mut datagram = Vec::with_capacity(30 + self.hostname.len());
+        self.push_header(&mut datagram, KIND_UPSET, check, allocation_bytes);
+        datagram.extend_from_slice(&(upset.index as u64).to_be_bytes());
+        datagram.push(upset.old_value);
+        datagram.push(upset.new_value);
+        datagram.push(upset.flipped_bits());
+        self.send(&datagram, "upset");
+    }
+
+    fn push_header(&self, datagram: &mut Vec<u8>, kind: u8, check: u64, allocation_bytes: usize) {
+        // Truncate on a char boundary so the hostname field stays valid UTF-8, as documented.
+        let mut cutoff = self.hostname.len().min(255);
+        while !self.hostname.is_char_boundary(cutoff) {
+            cutoff -= 1;
+        }
+        let hostname = &self.hostname.as_bytes()[..cutoff];
+
+        datagram.push(PROTOCOL_VERSION);
+        datagram.push(kind);
+        datagram.push(hostname.len() as u8);
+        datagram.extend_from_slice(hostname);
+        datagram.extend_from_slice(&check.to_be_bytes());
+        datagram.extend_from_slice(&(allocation_bytes as u64).to_be_bytes());
+    }
+
+    /// Sends `datagram` to the collector, logging (but not propagating) any failure so that a
+    /// flaky network never stops detection.
+    fn send(&self, datagram: &[u8], what: &str) {
+        if let Err(e) = self.socket.send(datagram) {
+            eprintln!("Failed to send {what} datagram to collector: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::detector::UpsetKind;
+
+    /// Binds a collector socket and a [`Reporter`] connected to it, so a test can assert on
+    /// the bytes the reporter actually puts on the wire.
+    fn collector_and_reporter() -> (UdpSocket, Reporter) {
+        let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut reporter = Reporter::connect(collector.local_addr().unwrap()).unwrap();
+        reporter.hostname = "test-host".to_owned();
+        (collector, reporter)
+    }
+
+    fn recv(collector: &UdpSocket) -> Vec<u8> {
+        let mut buf = [0u8; 512];
+        let n = collector.recv(&mut buf).unwrap();
+        buf[..n].to_vec()
+    }
+
+    #[test]
+    fn heartbeat_datagram_matches_documented_layout() {
+        let (collector, reporter) = collector_and_reporter();
+
+        reporter.report_heartbeat(42, 1024);
+        let datagram = recv(&collector);
+
+        assert_eq!(datagram[0], PROTOCOL_VERSION);
+        assert_eq!(datagram[1], KIND_HEARTBEAT);
+        assert_eq!(datagram[2], 9);
+        assert_eq!(&datagram[3..12], b"test-host");
+        assert_eq!(&datagram[12..20], &42u64.to_be_bytes());
+        assert_eq!(&datagram[20..28], &1024u64.to_be_bytes());
+        assert_eq!(datagram.len(), 28);
+    }
+
+    #[test]
+    fn upset_datagram_matches_documented_layout() {
+        let (collector, reporter) = collector_and_reporter();
+
+        let upset = Upset {
+            index: 7,
+            old_value: 0b0000_0001,
+            new_value: 0b0000_0011,
+            kind: UpsetKind::SingleBit,
+        };
+        reporter.report_upset(1, 2048, &upset);
+        let datagram = recv(&collector);
+
+        assert_eq!(datagram[1], KIND_UPSET);
+        assert_eq!(&datagram[28..36], &7u64.to_be_bytes());
+        assert_eq!(datagram[36], upset.old_value);
+        assert_eq!(datagram[37], upset.new_value);
+        assert_eq!(datagram[38], upset.flipped_bits());
+        assert_eq!(datagram.len(), 39);
+    }
+
+    #[test]
+    fn hostname_is_truncated_to_255_bytes_on_a_char_boundary() {
+        let (collector, mut reporter) = collector_and_reporter();
+        // Each "é" is 2 bytes, so byte 255 lands mid-character; the truncation loop must
+        // back off to the preceding char boundary instead of splitting it.
+        reporter.hostname = "é".repeat(200);
+
+        reporter.report_heartbeat(0, 0);
+        let datagram = recv(&collector);
+
+        let hostname_len = datagram[2] as usize;
+        assert!(hostname_len <= 255);
+        let hostname_bytes = &datagram[3..3 + hostname_len];
+        assert!(std::str::from_utf8(hostname_bytes).is_ok());
+    }
+}